@@ -3,7 +3,7 @@
 
 #![deny(missing_docs)]
 #![deny(warnings)]
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 
 use embedded_hal::digital::v2::InputPin;
 
@@ -29,6 +29,23 @@ pub enum Sensitivity {
     Low = 4,
 }
 
+/// The number of detents ("clicks") recognized within one full quadrature period of the encoder.
+/// `Sensitivity` only applies to [`StepMode::Full`]; [`StepMode::Quarter`] emits a detent on every
+/// valid transition regardless of `Sensitivity`, and [`StepMode::Half`] decodes detents from the
+/// stable states directly and ignores `Sensitivity` too.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StepMode {
+    /// A detent every `Sensitivity` valid transitions: one per full quadrature period with
+    /// [`Sensitivity::Low`], two with [`Sensitivity::Default`]. This matches the encoder's
+    /// original behaviour.
+    Full,
+    /// Two detents per quadrature period; the encoder is mechanically stable at both the
+    /// both-low (`00`) and both-high (`11`) states.
+    Half,
+    /// A detent on every quarter of a quadrature period, i.e. on every valid transition.
+    Quarter,
+}
+
 /// State table for recognizing valid rotary encoder values
 const STATES: [i8; 16] = [0, -1, 1, 0, 1, 0, 0, -1, -1, 0, 0, 1, 0, 1, -1, 0];
 
@@ -43,27 +60,43 @@ const DEFAULT_VELOCITY_DEC_FACTOR: f32 = 0.01;
 const DEFAULT_VELOCITY_ACTION_MS: u64 = 25;
 
 /// Rotary Encoder
-pub struct RotaryEncoder<DT, CLK> {
+pub struct RotaryEncoder<'a, DT, CLK> {
     pin_dt: DT,
     pin_clk: CLK,
     pos_calc: i8,
     sensitivity: Sensitivity,
     transition: u8,
     direction: Direction,
+    position: i32,
+    min_position: Option<i32>,
+    max_position: Option<i32>,
+    rollover: bool,
+    on_clockwise: Option<&'a mut dyn FnMut()>,
+    on_anticlockwise: Option<&'a mut dyn FnMut()>,
+    step_mode: StepMode,
+    last_stable: Option<u8>,
+    invert_dt: bool,
+    invert_clk: bool,
 }
 
 #[cfg(feature = "angular-velocity")]
 /// Rotary Encoder with velocity
-pub struct RotaryEncoderWithVelocity<DT, CLK> {
-    inner: RotaryEncoder<DT, CLK>,
+pub struct RotaryEncoderWithVelocity<'a, DT, CLK> {
+    inner: RotaryEncoder<'a, DT, CLK>,
     velocity: Velocity,
     velocity_inc_factor: f32,
     velocity_dec_factor: f32,
     velocity_action_ms: u64,
     previous_time: u64,
+    res: f32,
+    diameter: Option<f32>,
+    counter: i32,
+    counter_prev: i32,
+    time_prev: u64,
+    linear_speed: Option<f32>,
 }
 
-impl<DT, CLK> RotaryEncoder<DT, CLK>
+impl<'a, DT, CLK> RotaryEncoder<'a, DT, CLK>
 where
     DT: InputPin,
     CLK: InputPin,
@@ -77,14 +110,117 @@ where
             transition: 0,
             sensitivity: Sensitivity::Default,
             direction: Direction::None,
+            position: 0,
+            min_position: None,
+            max_position: None,
+            rollover: false,
+            on_clockwise: None,
+            on_anticlockwise: None,
+            step_mode: StepMode::Full,
+            last_stable: None,
+            invert_dt: false,
+            invert_clk: false,
         }
     }
 
+    /// Set the [`StepMode`], i.e. how many detents `update()` recognizes per quadrature period.
+    /// Defaults to [`StepMode::Full`].
+    pub fn set_step_mode(&mut self, step_mode: StepMode) {
+        self.step_mode = step_mode;
+    }
+
+    /// Invert the DT and/or CLK pin readings before they reach the state machine. Useful for
+    /// correcting reversed direction or swapped/inverted wiring in software, instead of
+    /// physically swapping wires or negating [`direction`](Self::direction) everywhere.
+    pub fn set_inverted(&mut self, dt: bool, clk: bool) {
+        self.invert_dt = dt;
+        self.invert_clk = clk;
+    }
+
+    /// Register a callback invoked exactly once from `update()` whenever a clockwise detent is
+    /// resolved. Because this typically runs from an interrupt context, keep the closure cheap
+    /// and allocation-free, e.g. incrementing a counter or setting an atomic flag rather than
+    /// polling [`direction`](Self::direction) from the main loop.
+    pub fn on_clockwise(&mut self, f: &'a mut dyn FnMut()) {
+        self.on_clockwise = Some(f);
+    }
+
+    /// Register a callback invoked exactly once from `update()` whenever an anticlockwise detent
+    /// is resolved. See [`on_clockwise`](Self::on_clockwise) for usage notes.
+    pub fn on_anticlockwise(&mut self, f: &'a mut dyn FnMut()) {
+        self.on_anticlockwise = Some(f);
+    }
+
     /// Set the sensitivity of the rotary encoder
     pub fn set_sensitivity(&mut self, sensitivity: Sensitivity) {
         self.sensitivity = sensitivity;
     }
 
+    /// Constrain [`position`](Self::position) to `min..=max`. Once set, `update()` will stop the
+    /// position at whichever bound it reaches, or wrap around to the opposite bound instead if
+    /// [`set_rollover`](Self::set_rollover) is enabled. If `min > max` the two are swapped, so the
+    /// resulting bounds are always a valid, non-inverted range.
+    pub fn set_bounds(&mut self, min: i32, max: i32) {
+        let (min, max) = if min <= max { (min, max) } else { (max, min) };
+        self.min_position = Some(min);
+        self.max_position = Some(max);
+        self.position = self.clamp_position(self.position);
+    }
+
+    /// Enable or disable rollover at the configured bounds. Has no effect unless
+    /// [`set_bounds`](Self::set_bounds) has been called. When enabled, incrementing past `max`
+    /// wraps the position to `min` and decrementing past `min` wraps it to `max`, rather than
+    /// clamping at the bound.
+    pub fn set_rollover(&mut self, rollover: bool) {
+        self.rollover = rollover;
+    }
+
+    /// Returns the current position of the RotaryEncoder. This is incremented or decremented by
+    /// one each time `update()` resolves a detent, and is constrained by any bounds set via
+    /// [`set_bounds`](Self::set_bounds).
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    /// Set the current position of the RotaryEncoder, clamping it to the configured bounds if any.
+    pub fn set_position(&mut self, position: i32) {
+        self.position = self.clamp_position(position);
+    }
+
+    /// Reset the position counter back to zero, clamped to the configured bounds if any (e.g. to
+    /// `min`, for a bounded counter whose range doesn't include zero).
+    pub fn reset(&mut self) {
+        self.position = self.clamp_position(0);
+    }
+
+    fn clamp_position(&self, position: i32) -> i32 {
+        match (self.min_position, self.max_position) {
+            (Some(min), Some(max)) => position.clamp(min, max),
+            _ => position,
+        }
+    }
+
+    fn step_position(&mut self, delta: i32) {
+        let next = self.position + delta;
+        self.position = match (self.min_position, self.max_position) {
+            (Some(min), Some(max)) if next > max => {
+                if self.rollover {
+                    min
+                } else {
+                    max
+                }
+            }
+            (Some(min), Some(max)) if next < min => {
+                if self.rollover {
+                    max
+                } else {
+                    min
+                }
+            }
+            _ => next,
+        };
+    }
+
     /// Borrow a mutable reference to the underlying InputPins. This is useful for clearing hardware interrupts.
     pub fn borrow_pins(&mut self) -> (&mut DT, &mut CLK) {
         (&mut self.pin_dt, &mut self.pin_clk)
@@ -95,30 +231,75 @@ where
         (self.pin_dt, self.pin_clk)
     }
 
-    /// Update the state machine of the RotaryEncoder. This should be called ideally from an interrupt vector
-    /// when either the DT or CLK pins state changes. This function will update the RotaryEncoder's Direction
-    pub fn update(&mut self) {
-        let dt_state = self.pin_dt.is_high().unwrap_or_default() as u8;
-        let clk_state = self.pin_clk.is_high().unwrap_or_default() as u8;
-
+    fn process_transition(&mut self, dt_state: u8, clk_state: u8) {
         let current = (dt_state << 1) | clk_state;
         self.transition = (self.transition << 2) | current;
         let index = (self.transition & 0x0F) as usize;
-        self.pos_calc += STATES[index];
-
-        let sensitivity = self.sensitivity as i8;
-        if self.pos_calc == sensitivity || self.pos_calc == -sensitivity {
-            self.direction = if self.pos_calc == sensitivity {
-                Direction::Clockwise
-            } else {
-                Direction::Anticlockwise
-            };
-
-            self.pos_calc = 0;
-            return;
-        }
+        let delta = STATES[index];
+        self.pos_calc = self.pos_calc.saturating_add(delta);
 
         self.direction = Direction::None;
+
+        match self.step_mode {
+            StepMode::Full => {
+                let sensitivity = self.sensitivity as i8;
+                if self.pos_calc == sensitivity || self.pos_calc == -sensitivity {
+                    self.direction = if self.pos_calc == sensitivity {
+                        Direction::Clockwise
+                    } else {
+                        Direction::Anticlockwise
+                    };
+                    self.pos_calc = 0;
+                }
+            }
+            StepMode::Quarter => {
+                if delta != 0 {
+                    self.direction = if delta > 0 {
+                        Direction::Clockwise
+                    } else {
+                        Direction::Anticlockwise
+                    };
+                    self.pos_calc = 0;
+                }
+            }
+            StepMode::Half => {
+                // Both outputs low or both high: the encoder is at a stable detent.
+                if current == 0b00 || current == 0b11 {
+                    match self.last_stable {
+                        // First stable read: just learn the baseline, nothing has moved yet.
+                        None => self.last_stable = Some(current),
+                        Some(last_stable) if current != last_stable => {
+                            self.direction = if self.pos_calc > 0 {
+                                Direction::Clockwise
+                            } else if self.pos_calc < 0 {
+                                Direction::Anticlockwise
+                            } else {
+                                Direction::None
+                            };
+                            self.last_stable = Some(current);
+                        }
+                        Some(_) => {}
+                    }
+                    self.pos_calc = 0;
+                }
+            }
+        }
+
+        match self.direction {
+            Direction::Clockwise => {
+                self.step_position(1);
+                if let Some(f) = self.on_clockwise.as_mut() {
+                    f();
+                }
+            }
+            Direction::Anticlockwise => {
+                self.step_position(-1);
+                if let Some(f) = self.on_anticlockwise.as_mut() {
+                    f();
+                }
+            }
+            Direction::None => {}
+        }
     }
 
     /// Returns the current Direction of the RotaryEncoder
@@ -127,15 +308,42 @@ where
     }
 }
 
+impl<'a, DT, CLK, E> RotaryEncoder<'a, DT, CLK>
+where
+    DT: InputPin<Error = E>,
+    CLK: InputPin<Error = E>,
+{
+    /// Update the state machine of the RotaryEncoder, returning an error if either pin fails to
+    /// read rather than silently treating the failure as a low level. This is the fallible
+    /// counterpart to [`update`](Self::update).
+    pub fn try_update(&mut self) -> Result<Direction, E> {
+        let dt_state = (self.pin_dt.is_high()? ^ self.invert_dt) as u8;
+        let clk_state = (self.pin_clk.is_high()? ^ self.invert_clk) as u8;
+        self.process_transition(dt_state, clk_state);
+        Ok(self.direction)
+    }
+
+    /// Update the state machine of the RotaryEncoder. This should be called ideally from an interrupt vector
+    /// when either the DT or CLK pins state changes. This function will update the RotaryEncoder's Direction.
+    /// A pin read error is silently ignored; use [`try_update`](Self::try_update) to observe and react to
+    /// read failures instead.
+    pub fn update(&mut self) {
+        let _ = self.try_update();
+    }
+}
+
 #[cfg(feature = "angular-velocity")]
-impl<DT, CLK> RotaryEncoderWithVelocity<DT, CLK>
+impl<'a, DT, CLK> RotaryEncoderWithVelocity<'a, DT, CLK>
 where
     DT: InputPin,
     CLK: InputPin,
 {
-    /// Initiates a new Rotary Encoder with velocity, taking two InputPins [`InputPin`](https://docs.rs/embedded-hal/0.2.3/embedded_hal/digital/v2/trait.InputPin.html).
+    /// Initiates a new Rotary Encoder with velocity, taking two InputPins [`InputPin`](https://docs.rs/embedded-hal/0.2.3/embedded_hal/digital/v2/trait.InputPin.html)
+    /// and the encoder's detents-per-revolution `res`, used by [`sample`](Self::sample) to compute a
+    /// physical angular velocity. `res` must be greater than `0.0`; a zero value makes `sample()`
+    /// return `inf`/`NaN` angular velocity indefinitely, and a negative one flips its sign.
     /// Optionally the behaviour of the angular velocity can be modified:
-    pub fn new(pin_dt: DT, pin_clk: CLK) -> Self {
+    pub fn new(pin_dt: DT, pin_clk: CLK, res: f32) -> Self {
         RotaryEncoderWithVelocity {
             inner: RotaryEncoder::new(pin_dt, pin_clk),
             velocity: 0.0,
@@ -143,9 +351,57 @@ where
             velocity_dec_factor: DEFAULT_VELOCITY_DEC_FACTOR,
             velocity_action_ms: DEFAULT_VELOCITY_ACTION_MS,
             previous_time: 0,
+            res,
+            diameter: None,
+            counter: 0,
+            counter_prev: 0,
+            time_prev: 0,
+            linear_speed: None,
         }
     }
 
+    /// Set the diameter of the wheel or knob attached to the encoder shaft. Once set,
+    /// [`sample`](Self::sample) also computes a linear speed retrievable via
+    /// [`linear_speed`](Self::linear_speed).
+    pub fn set_diameter(&mut self, diameter: f32) {
+        self.diameter = Some(diameter);
+    }
+
+    /// Sample the physical angular velocity of the encoder in degrees/sec, computed from the
+    /// number of detents resolved by `update()` since the previous call and the elapsed time.
+    ///
+    /// Returns `0.0` without updating the internal baseline if `current_time_ms` has not advanced
+    /// since the last sample, e.g. because it was called twice within the same millisecond. A
+    /// reverse rotation yields a negative velocity. If a wheel diameter was configured via
+    /// [`set_diameter`](Self::set_diameter), the corresponding linear speed is also updated and can
+    /// be read back with [`linear_speed`](Self::linear_speed).
+    pub fn sample(&mut self, current_time_ms: u64) -> f32 {
+        let dt_ms = current_time_ms.saturating_sub(self.time_prev);
+        if dt_ms == 0 {
+            return 0.0;
+        }
+
+        let delta = self.counter - self.counter_prev;
+        let dt = dt_ms as f32 / 1000.0;
+        let revolutions = delta as f32 / self.res;
+        let angular_velocity = revolutions * 360.0 / dt;
+
+        self.linear_speed = self
+            .diameter
+            .map(|diameter| core::f32::consts::PI * diameter * revolutions / dt);
+
+        self.counter_prev = self.counter;
+        self.time_prev = current_time_ms;
+
+        angular_velocity
+    }
+
+    /// Returns the linear speed computed by the last [`sample`](Self::sample) call, or `None` if
+    /// no wheel diameter was configured via [`set_diameter`](Self::set_diameter).
+    pub fn linear_speed(&self) -> Option<f32> {
+        self.linear_speed
+    }
+
     /// Set the velocity_inc_factor. How quickly the velocity increases to 1.0.
     pub fn set_velocity_inc_factor(&mut self, inc_factor: f32) {
         self.velocity_inc_factor = inc_factor;
@@ -181,7 +437,7 @@ where
     }
 
     /// Borrow a reference to the underlying RotaryEncoder. Useful for configuring the RotaryEncoder
-    pub fn borrow_inner(&mut self) -> &mut RotaryEncoder<DT, CLK> {
+    pub fn borrow_inner(&mut self) -> &mut RotaryEncoder<'a, DT, CLK> {
         &mut self.inner
     }
 
@@ -190,35 +446,222 @@ where
         self.inner.release()
     }
 
-    /// Update the state machine of the RotaryEncoder. This should be called ideally from an interrupt vector
-    /// when either the DT or CLK pins state changes. This function will update the RotaryEncoder's
-    /// Direction and current Angular Velocity.
+    /// Returns the current Direction of the RotaryEncoder
+    pub fn direction(&self) -> Direction {
+        self.inner.direction
+    }
+
+    /// Returns the current angular velocity of the RotaryEncoder
+    /// The Angular Velocity is a value between 0.0 and 1.0
+    /// This is useful for incrementing/decrementing a value in an exponential fashion
+    pub fn velocity(&self) -> Velocity {
+        self.velocity
+    }
+}
+
+#[cfg(feature = "angular-velocity")]
+impl<'a, DT, CLK, E> RotaryEncoderWithVelocity<'a, DT, CLK>
+where
+    DT: InputPin<Error = E>,
+    CLK: InputPin<Error = E>,
+{
+    /// Update the state machine of the RotaryEncoder, returning an error if either pin fails to read.
+    /// This is the fallible counterpart to [`update`](Self::update).
     /// * `current_time` - Current timestamp in ms (strictly monotonously increasing)
-    pub fn update(&mut self, current_time: u64) {
-        self.inner.update();
+    pub fn try_update(&mut self, current_time: u64) -> Result<Direction, E> {
+        let direction = self.inner.try_update()?;
 
-        if self.inner.direction() != Direction::None {
+        match direction {
+            Direction::Clockwise => self.counter += 1,
+            Direction::Anticlockwise => self.counter -= 1,
+            Direction::None => {}
+        }
+
+        if direction != Direction::None {
             if current_time - self.previous_time < self.velocity_action_ms && self.velocity < 1.0 {
                 self.velocity += self.velocity_inc_factor;
                 if self.velocity > 1.0 {
                     self.velocity = 1.0;
                 }
             }
-            return;
+            return Ok(direction);
         }
 
         self.previous_time = current_time;
+        Ok(direction)
     }
 
-    /// Returns the current Direction of the RotaryEncoder
-    pub fn direction(&self) -> Direction {
-        self.inner.direction
+    /// Update the state machine of the RotaryEncoder. This should be called ideally from an interrupt vector
+    /// when either the DT or CLK pins state changes. This function will update the RotaryEncoder's
+    /// Direction and current Angular Velocity. A pin read error is silently ignored; use
+    /// [`try_update`](Self::try_update) to observe and react to read failures instead.
+    /// * `current_time` - Current timestamp in ms (strictly monotonously increasing)
+    pub fn update(&mut self, current_time: u64) {
+        let _ = self.try_update(current_time);
     }
+}
 
-    /// Returns the current angular velocity of the RotaryEncoder
-    /// The Angular Velocity is a value between 0.0 and 1.0
-    /// This is useful for incrementing/decrementing a value in an exponential fashion
-    pub fn velocity(&self) -> Velocity {
-        self.velocity
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+    use core::convert::Infallible;
+
+    struct MockPin<'a> {
+        state: &'a Cell<bool>,
+    }
+
+    impl<'a> InputPin for MockPin<'a> {
+        type Error = Infallible;
+
+        fn is_high(&self) -> Result<bool, Infallible> {
+            Ok(self.state.get())
+        }
+
+        fn is_low(&self) -> Result<bool, Infallible> {
+            Ok(!self.state.get())
+        }
+    }
+
+    fn set_pins(dt: &Cell<bool>, clk: &Cell<bool>, dt_state: bool, clk_state: bool) {
+        dt.set(dt_state);
+        clk.set(clk_state);
+    }
+
+    #[test]
+    fn full_step_resolves_clockwise() {
+        let dt = Cell::new(false);
+        let clk = Cell::new(false);
+        let mut encoder = RotaryEncoder::new(MockPin { state: &dt }, MockPin { state: &clk });
+
+        set_pins(&dt, &clk, true, false);
+        encoder.update();
+        assert_eq!(encoder.direction(), Direction::None);
+
+        set_pins(&dt, &clk, true, true);
+        encoder.update();
+        assert_eq!(encoder.direction(), Direction::Clockwise);
+        assert_eq!(encoder.position(), 1);
+    }
+
+    #[test]
+    fn full_step_resolves_anticlockwise() {
+        let dt = Cell::new(false);
+        let clk = Cell::new(false);
+        let mut encoder = RotaryEncoder::new(MockPin { state: &dt }, MockPin { state: &clk });
+
+        set_pins(&dt, &clk, false, true);
+        encoder.update();
+        assert_eq!(encoder.direction(), Direction::None);
+
+        set_pins(&dt, &clk, true, true);
+        encoder.update();
+        assert_eq!(encoder.direction(), Direction::Anticlockwise);
+        assert_eq!(encoder.position(), -1);
+    }
+
+    #[test]
+    fn quarter_step_resolves_on_every_valid_transition() {
+        let dt = Cell::new(false);
+        let clk = Cell::new(false);
+        let mut encoder = RotaryEncoder::new(MockPin { state: &dt }, MockPin { state: &clk });
+        encoder.set_step_mode(StepMode::Quarter);
+
+        set_pins(&dt, &clk, true, false);
+        encoder.update();
+        assert_eq!(encoder.direction(), Direction::Clockwise);
+        assert_eq!(encoder.position(), 1);
+    }
+
+    #[test]
+    fn half_step_learns_baseline_from_first_stable_read_without_firing() {
+        let dt = Cell::new(false);
+        let clk = Cell::new(false);
+        let mut encoder = RotaryEncoder::new(MockPin { state: &dt }, MockPin { state: &clk });
+        encoder.set_step_mode(StepMode::Half);
+
+        // The encoder idles at the both-high (11) stable state; the first read must only learn
+        // this as the baseline, not fire a spurious direction relative to an assumed 00 baseline.
+        set_pins(&dt, &clk, true, true);
+        encoder.update();
+        assert_eq!(encoder.direction(), Direction::None);
+        assert_eq!(encoder.position(), 0);
+    }
+
+    #[test]
+    fn half_step_resolves_direction_relative_to_learned_baseline() {
+        let dt = Cell::new(false);
+        let clk = Cell::new(false);
+        let mut encoder = RotaryEncoder::new(MockPin { state: &dt }, MockPin { state: &clk });
+        encoder.set_step_mode(StepMode::Half);
+
+        // Learn the baseline at the both-high (11) stable state.
+        set_pins(&dt, &clk, true, true);
+        encoder.update();
+
+        // Move through the intermediate state to the both-low (00) stable state.
+        set_pins(&dt, &clk, true, false);
+        encoder.update();
+        assert_eq!(encoder.direction(), Direction::None);
+
+        set_pins(&dt, &clk, false, false);
+        encoder.update();
+        assert_eq!(encoder.direction(), Direction::Anticlockwise);
+        assert_eq!(encoder.position(), -1);
+    }
+
+    #[test]
+    fn set_bounds_normalizes_inverted_range() {
+        let dt = Cell::new(false);
+        let clk = Cell::new(false);
+        let mut encoder = RotaryEncoder::new(MockPin { state: &dt }, MockPin { state: &clk });
+
+        encoder.set_bounds(10, 5);
+        encoder.set_position(100);
+        assert_eq!(encoder.position(), 10);
+
+        encoder.reset();
+        assert_eq!(encoder.position(), 5);
+    }
+
+    #[cfg(feature = "angular-velocity")]
+    #[test]
+    fn sample_short_circuits_when_time_has_not_advanced() {
+        let dt = Cell::new(false);
+        let clk = Cell::new(false);
+        let mut encoder =
+            RotaryEncoderWithVelocity::new(MockPin { state: &dt }, MockPin { state: &clk }, 20.0);
+
+        assert_eq!(encoder.sample(0), 0.0);
+    }
+
+    #[cfg(feature = "angular-velocity")]
+    #[test]
+    fn sample_reports_negative_velocity_for_reverse_rotation() {
+        let dt = Cell::new(false);
+        let clk = Cell::new(false);
+        let mut encoder =
+            RotaryEncoderWithVelocity::new(MockPin { state: &dt }, MockPin { state: &clk }, 20.0);
+
+        encoder.counter = -4;
+        let angular_velocity = encoder.sample(1000);
+        assert!((angular_velocity - -72.0).abs() < 1e-4);
+    }
+
+    #[cfg(feature = "angular-velocity")]
+    #[test]
+    fn sample_computes_linear_speed_once_diameter_is_set() {
+        let dt = Cell::new(false);
+        let clk = Cell::new(false);
+        let mut encoder =
+            RotaryEncoderWithVelocity::new(MockPin { state: &dt }, MockPin { state: &clk }, 20.0);
+        encoder.set_diameter(1.0);
+
+        encoder.counter = 4;
+        encoder.sample(1000);
+
+        let linear_speed = encoder.linear_speed().expect("diameter was configured");
+        let expected = core::f32::consts::PI * 1.0 * (4.0 / 20.0) / 1.0;
+        assert!((linear_speed - expected).abs() < 1e-4);
     }
 }